@@ -0,0 +1,34 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::fmt;
+
+/// Errors returned by fallible [`Publisher`] operations.
+///
+/// [`Publisher`]: crate::Publisher
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PubserveError {
+    /// Returned by [`Publisher::subscribe`] and [`Publisher::subscribe_channel`] once the
+    /// `max_subscribers` cap configured via [`Publisher::with_max_subscribers`] has been reached.
+    ///
+    /// [`Publisher::subscribe`]: crate::Publisher::subscribe
+    /// [`Publisher::subscribe_channel`]: crate::Publisher::subscribe_channel
+    /// [`Publisher::with_max_subscribers`]: crate::Publisher::with_max_subscribers
+    MaximumSubscribersReached,
+}
+
+impl fmt::Display for PubserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PubserveError::MaximumSubscribersReached => {
+                write!(
+                    f,
+                    "the publisher's maximum number of subscribers has been reached"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PubserveError {}
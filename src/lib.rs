@@ -2,6 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+mod error;
+mod subscription;
+
+pub use error::PubserveError;
+pub use subscription::Subscription;
+
 #[cfg(not(feature = "send"))]
 use std::rc::Rc;
 #[cfg(not(feature = "send"))]
@@ -56,30 +62,134 @@ pub trait Subscriber<T> {
 /// publisher.publish("Hello, World!".to_string()); // .await, if async feature is enabled
 /// ```
 pub struct Publisher<T> {
-    subscribers: Vec<ReferenceCounted<dyn Subscriber<T>>>,
+    // A `BTreeMap` instead of a `HashMap`: subscriber ids are assigned in increasing order, so
+    // iterating it visits subscribers in subscription order, which `publish_concurrent`'s docs
+    // promise of `publish`.
+    subscribers: std::collections::BTreeMap<u32, ReferenceCounted<dyn Subscriber<T>>>,
+    channels: subscription::Registry<T>,
+    next_channel_id: u32,
+    next_subscriber_id: u32,
+    channel_capacity: Option<usize>,
+    max_subscribers: Option<usize>,
+    #[cfg(all(feature = "sink", feature = "async"))]
+    pending: std::collections::VecDeque<T>,
+    #[cfg(all(feature = "sink", feature = "async"))]
+    flush_fut: Option<std::pin::Pin<Box<dyn std::future::Future<Output = ()>>>>,
 }
 
+#[cfg(all(feature = "sink", feature = "async"))]
+// `pending` stores `T` by value, which would otherwise make `Publisher<T>` conditionally
+// `!Unpin` for `!Unpin` `T`. Nothing in `Publisher` relies on pin-structural guarantees over its
+// fields (the only pinned field, `flush_fut`, is a `Pin<Box<_>>` and therefore already `Unpin`
+// regardless of `T`), so it's sound to unconditionally opt back in. Required for the `Sink` impl
+// below, which calls `Pin::get_mut` in `start_send`/`poll_flush`.
+impl<T> Unpin for Publisher<T> {}
+
 impl<T> std::default::Default for Publisher<T> {
     fn default() -> Self {
         Self {
-            subscribers: Vec::new(),
+            subscribers: std::collections::BTreeMap::new(),
+            channels: subscription::new_registry(),
+            next_channel_id: 0,
+            next_subscriber_id: 0,
+            channel_capacity: None,
+            max_subscribers: None,
+            #[cfg(all(feature = "sink", feature = "async"))]
+            pending: std::collections::VecDeque::new(),
+            #[cfg(all(feature = "sink", feature = "async"))]
+            flush_fut: None,
+        }
+    }
+}
+
+/// Cloning a `Publisher` does not deep-copy its subscribers: trait-based subscribers are held
+/// behind [`ReferenceCounted`] pointers, so the clone's subscriber map still points at the same
+/// subscriber instances (though the two publishers' maps are independent from then on — removing
+/// one's subscriber doesn't remove the other's). The channel-backed subscription registry, on the
+/// other hand, is itself a [`ReferenceCounted`] pointer, so clones keep sharing the exact same
+/// registry and `subscribe_channel` on either delivers to both.
+impl<T: Clone> Clone for Publisher<T> {
+    fn clone(&self) -> Self {
+        Self {
+            subscribers: self.subscribers.clone(),
+            channels: self.channels.clone(),
+            next_channel_id: self.next_channel_id,
+            next_subscriber_id: self.next_subscriber_id,
+            channel_capacity: self.channel_capacity,
+            max_subscribers: self.max_subscribers,
+            #[cfg(all(feature = "sink", feature = "async"))]
+            pending: self.pending.clone(),
+            #[cfg(all(feature = "sink", feature = "async"))]
+            flush_fut: None,
         }
     }
 }
 
+/// An opaque handle returned by [`Publisher::subscribe`], identifying the subscriber it was
+/// returned for so it can be removed again with [`Publisher::unsubscribe_token`].
+///
+/// Unlike [`Publisher::unsubscribe`], which relies on the caller still holding a
+/// [`ReferenceCounted`] pointer to the exact same subscriber instance, a `SubscriptionToken`
+/// unambiguously identifies the subscription regardless of how many clones of the subscriber
+/// pointer exist or whether the caller kept one around at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionToken(u32);
+
 impl<T> Publisher<T> {
     /// Create a new Publisher with no subscribers.
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Bound channel-backed [`Subscription`]s to `capacity` messages each, turning them into
+    /// ring buffers instead of unbounded queues.
+    ///
+    /// Once a subscription's buffer is full, `publish` never blocks or waits for it to be read:
+    /// the oldest buffered message is dropped to make room, and [`Subscription::lagged`] is
+    /// incremented so the consumer can tell it missed data. This is meant for high-throughput,
+    /// latest-value-matters-most streams (market data, live sensor feeds) where a single slow
+    /// subscriber must not be allowed to serialize the rest of the system.
+    ///
+    /// Subscribers added with [`Publisher::subscribe`] are unaffected; this only bounds
+    /// channel-backed subscriptions created afterwards with [`Publisher::subscribe_channel`].
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.channel_capacity = Some(capacity);
+        self
+    }
+
+    /// Alias for [`Publisher::with_capacity`], matching the naming other pubsub crates (e.g.
+    /// karyon) use for the same per-subscriber channel depth.
+    pub fn with_buffer_size(self, size: usize) -> Self {
+        self.with_capacity(size)
+    }
+
+    /// Cap the number of subscribers this Publisher will accept, whether added with
+    /// [`Publisher::subscribe`] or [`Publisher::subscribe_channel`]. Once the cap is reached,
+    /// both methods return [`PubserveError::MaximumSubscribersReached`] instead of adding the
+    /// subscriber. Useful for bounding pubserve's worst-case memory use in embedded or otherwise
+    /// resource-constrained contexts.
+    pub fn with_max_subscribers(mut self, max_subscribers: usize) -> Self {
+        self.max_subscribers = Some(max_subscribers);
+        self
+    }
+
     /// Check if the Publisher has any subscribers.
     pub fn has_subscribers(&self) -> bool {
         !self.subscribers.is_empty()
     }
 
+    fn subscriber_count(&self) -> usize {
+        self.subscribers.len() + subscription::count(&self.channels)
+    }
+
     /// Add a subscriber to the Publishers list of subscribers. The subscriber will be notified
-    /// when the Publisher sends a message.
+    /// when the Publisher sends a message. Returns a [`SubscriptionToken`] identifying this
+    /// subscription, which is the recommended way to remove it again with
+    /// [`Publisher::unsubscribe_token`] — unlike [`Publisher::unsubscribe`], it doesn't depend
+    /// on the caller still holding a pointer to the exact same subscriber instance.
+    ///
+    /// Returns [`PubserveError::MaximumSubscribersReached`] instead if this Publisher was built
+    /// with [`Publisher::with_max_subscribers`] and is already at its cap.
     ///
     /// ## Example
     ///
@@ -103,20 +213,27 @@ impl<T> Publisher<T> {
     /// let mut publisher = Publisher::<String>::new();
     /// let subscriber = MySubscriber;
     /// let rc_subscriber = pubserve::ReferenceCounted::new(subscriber);
-    /// publisher.subscribe(rc_subscriber.clone());
+    /// let token = publisher.subscribe(rc_subscriber.clone()).unwrap();
     /// // This will print "Received this message: Hello, World!"
     /// publisher.publish("Hello, World!".to_string());
-    /// publisher.unsubscribe(rc_subscriber.clone());
+    /// publisher.unsubscribe_token(token);
     /// // The subscriber has been removed, so this will not print anything.
     /// publisher.publish("Hello, World!".to_string());
-    ///
-    /// // The following subscribing/unsubscribing will NOT work. If you do not understand why,
-    /// // please read up on how reference counting works in Rust.
-    /// publisher.subscribe(rc_subscriber.clone());
-    /// publisher.unsubscribe(pubserve::ReferenceCounted::new(MySubscriber));
     /// ```
-    pub fn subscribe(&mut self, subscriber: ReferenceCounted<dyn Subscriber<T>>) {
-        self.subscribers.push(subscriber);
+    pub fn subscribe(
+        &mut self,
+        subscriber: ReferenceCounted<dyn Subscriber<T>>,
+    ) -> Result<SubscriptionToken, PubserveError> {
+        if self
+            .max_subscribers
+            .is_some_and(|max| self.subscriber_count() >= max)
+        {
+            return Err(PubserveError::MaximumSubscribersReached);
+        }
+        let id = self.next_subscriber_id;
+        self.next_subscriber_id = self.next_subscriber_id.wrapping_add(1);
+        self.subscribers.insert(id, subscriber);
+        Ok(SubscriptionToken(id))
     }
 
     /// Remove a subscriber from the Publishers list of subscribers. The subscriber will no longer
@@ -125,7 +242,9 @@ impl<T> Publisher<T> {
     /// Important: This method uses the `ReferenceCounted::ptr_eq` method to compare the pointers
     /// of the subscriber to remove with the pointers of the subscribers in the list. The
     /// [ReferenceCounted] subscriber passed in must point to the same address as the subscriber
-    /// that was added to the list.
+    /// that was added to the list. Prefer [`Publisher::unsubscribe_token`] with the
+    /// [`SubscriptionToken`] returned by [`Publisher::subscribe`] if you can, since it does not
+    /// have this limitation.
     ///
     /// ## Example
     ///
@@ -149,7 +268,7 @@ impl<T> Publisher<T> {
     /// let mut publisher = Publisher::<String>::new();
     /// let subscriber = MySubscriber;
     /// let rc_subscriber = pubserve::ReferenceCounted::new(subscriber);
-    /// publisher.subscribe(rc_subscriber.clone());
+    /// publisher.subscribe(rc_subscriber.clone()).unwrap();
     /// // This will print "Received this message: Hello, World!"
     /// publisher.publish("Hello, World!".to_string());
     /// publisher.unsubscribe(rc_subscriber.clone());
@@ -158,28 +277,163 @@ impl<T> Publisher<T> {
     ///
     /// // The following subscribing/unsubscribing will NOT work. If you do not understand why,
     /// // please read up on how reference counting works in Rust.
-    /// publisher.subscribe(rc_subscriber.clone());
+    /// publisher.subscribe(rc_subscriber.clone()).unwrap();
     /// publisher.unsubscribe(pubserve::ReferenceCounted::new(MySubscriber));
     /// ```
     pub fn unsubscribe(&mut self, subscriber: ReferenceCounted<dyn Subscriber<T>>) {
         self.subscribers
-            .retain(|s| !ReferenceCounted::ptr_eq(s, &subscriber));
+            .retain(|_, s| !ReferenceCounted::ptr_eq(s, &subscriber));
+    }
+
+    /// Remove a subscriber by the [`SubscriptionToken`] returned from [`Publisher::subscribe`].
+    /// Unlike [`Publisher::unsubscribe`], this does not require the caller to still hold a
+    /// pointer to the subscriber.
+    pub fn unsubscribe_token(&mut self, token: SubscriptionToken) {
+        self.subscribers.remove(&token.0);
+    }
+
+    /// Subscribe to this Publisher without implementing the [`Subscriber`] trait. Returns a
+    /// [`Subscription`] handle that receives a clone of every message published from now on,
+    /// which can be read with [`Subscription::recv`]/[`Subscription::try_recv`].
+    ///
+    /// This is a pull-based alternative to the push-based `Subscriber` trait, useful for ad-hoc
+    /// consumers that don't want to define a type just to receive messages.
+    ///
+    /// Dropping the returned `Subscription` unsubscribes it.
+    ///
+    /// Returns [`PubserveError::MaximumSubscribersReached`] instead if this Publisher was built
+    /// with [`Publisher::with_max_subscribers`] and is already at its cap.
+    ///
+    /// ## Example
+    ///
+    /// The example assumes that the `async` feature is not enabled. If you have enabled the
+    /// `async` feature, you additionally need to `.await` the `publish` call. Otherwise, the
+    /// example is identical.
+    ///
+    /// ```
+    /// use pubserve::Publisher;
+    ///
+    /// let mut publisher = Publisher::<String>::new();
+    /// let subscription = publisher.subscribe_channel().unwrap();
+    /// publisher.publish("Hello, World!".to_string());
+    /// assert_eq!(subscription.try_recv(), Some("Hello, World!".to_string()));
+    /// ```
+    pub fn subscribe_channel(&mut self) -> Result<Subscription<T>, PubserveError> {
+        if self
+            .max_subscribers
+            .is_some_and(|max| self.subscriber_count() >= max)
+        {
+            return Err(PubserveError::MaximumSubscribersReached);
+        }
+        let id = self.next_channel_id;
+        self.next_channel_id = self.next_channel_id.wrapping_add(1);
+        Ok(Subscription::new(
+            id,
+            self.channels.clone(),
+            self.channel_capacity,
+        ))
     }
 
     #[cfg(not(feature = "async"))]
-    /// Publish a message to all subscribers.
-    pub fn publish(&self, message: T) {
-        for subscriber in &self.subscribers {
+    /// Publish a message to all subscribers, including channel-backed [`Subscription`]s.
+    pub fn publish(&self, message: T)
+    where
+        T: Clone,
+    {
+        for subscriber in self.subscribers.values() {
             subscriber.update(&message);
         }
+        subscription::broadcast(&self.channels, &message);
     }
 
     #[cfg(feature = "async")]
-    /// Publish a message to all subscribers.
-    pub async fn publish(&self, message: T) {
-        for subscriber in &self.subscribers {
+    /// Publish a message to all subscribers, including channel-backed [`Subscription`]s.
+    pub async fn publish(&self, message: T)
+    where
+        T: Clone,
+    {
+        for subscriber in self.subscribers.values() {
             subscriber.update(&message).await;
         }
+        subscription::broadcast(&self.channels, &message);
+    }
+
+    #[cfg(feature = "async")]
+    /// Publish a message to all subscribers concurrently instead of awaiting each `update` one
+    /// after another. This means a single slow subscriber no longer stalls delivery to the rest.
+    ///
+    /// No guarantees are made about the order in which subscribers observe the message relative
+    /// to one another. If you need subscribers to be notified in subscription order, use
+    /// [`Publisher::publish`] instead.
+    pub async fn publish_concurrent(&self, message: T)
+    where
+        T: Clone,
+    {
+        futures::future::join_all(
+            self.subscribers
+                .values()
+                .map(|subscriber| subscriber.update(&message)),
+        )
+        .await;
+        subscription::broadcast(&self.channels, &message);
+    }
+}
+
+#[cfg(all(feature = "sink", feature = "async"))]
+impl<T> Drop for Publisher<T> {
+    fn drop(&mut self) {
+        subscription::close(&self.channels);
+    }
+}
+
+#[cfg(all(feature = "sink", feature = "async"))]
+/// Lets a [`Publisher`] be driven by `futures` combinators such as `.forward()`, instead of
+/// calling [`Publisher::publish`] by hand. Buffered messages are delivered to every subscriber,
+/// trait-based and channel-backed alike, on [`futures::SinkExt::flush`]/`poll_flush`.
+impl<T: Clone + 'static> futures::Sink<T> for Publisher<T> {
+    type Error = std::convert::Infallible;
+
+    fn poll_ready(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: std::pin::Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        self.get_mut().pending.push_back(item);
+        Ok(())
+    }
+
+    fn poll_flush(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        loop {
+            if this.flush_fut.is_none() {
+                let Some(message) = this.pending.pop_front() else {
+                    return std::task::Poll::Ready(Ok(()));
+                };
+                let subscribers: Vec<_> = this.subscribers.values().cloned().collect();
+                let channels = this.channels.clone();
+                this.flush_fut = Some(Box::pin(async move {
+                    futures::future::join_all(subscribers.iter().map(|s| s.update(&message))).await;
+                    subscription::broadcast(&channels, &message);
+                }));
+            }
+            match this.flush_fut.as_mut().unwrap().as_mut().poll(cx) {
+                std::task::Poll::Ready(()) => this.flush_fut = None,
+                std::task::Poll::Pending => return std::task::Poll::Pending,
+            }
+        }
+    }
+
+    fn poll_close(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.poll_flush(cx)
     }
 }
 
@@ -197,12 +451,30 @@ fn test_publisher() {
     let mut publisher = Publisher::<String>::new();
     let subscriber = MySubscriber;
     let subscriber = ReferenceCounted::new(subscriber);
-    publisher.subscribe(subscriber.clone());
+    publisher.subscribe(subscriber.clone()).unwrap();
     publisher.publish("Hello, World!".to_string());
     publisher.unsubscribe(subscriber);
     publisher.publish("Hello, World!".to_string());
 }
 
+#[cfg(test)]
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_unsubscribe_token() {
+    struct MySubscriber;
+    impl Subscriber<i32> for MySubscriber {
+        fn update(&self, _message: &i32) {}
+    }
+
+    let mut publisher = Publisher::<i32>::new();
+    let token = publisher
+        .subscribe(ReferenceCounted::new(MySubscriber))
+        .unwrap();
+    assert!(publisher.has_subscribers());
+    publisher.unsubscribe_token(token);
+    assert!(!publisher.has_subscribers());
+}
+
 #[cfg(test)]
 #[cfg(feature = "async")]
 #[tokio::test]
@@ -218,8 +490,262 @@ async fn test_publisher() {
     let mut publisher = Publisher::<String>::new();
     let subscriber = MySubscriber;
     let subscriber = ReferenceCounted::new(subscriber);
-    publisher.subscribe(subscriber.clone());
+    publisher.subscribe(subscriber.clone()).unwrap();
     publisher.publish("Hello, World!".to_string()).await;
     publisher.unsubscribe(subscriber);
     publisher.publish("Hello, World!".to_string()).await;
 }
+
+#[cfg(test)]
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_unsubscribe_token() {
+    struct MySubscriber;
+    #[async_trait::async_trait]
+    impl Subscriber<i32> for MySubscriber {
+        async fn update(&self, _message: &i32) {}
+    }
+
+    let mut publisher = Publisher::<i32>::new();
+    let token = publisher
+        .subscribe(ReferenceCounted::new(MySubscriber))
+        .unwrap();
+    assert!(publisher.has_subscribers());
+    publisher.unsubscribe_token(token);
+    assert!(!publisher.has_subscribers());
+}
+
+#[cfg(test)]
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_publish_concurrent() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct SlowSubscriber(ReferenceCounted<AtomicU32>);
+    #[async_trait::async_trait]
+    impl Subscriber<u32> for SlowSubscriber {
+        async fn update(&self, message: &u32) {
+            // Yield once so `join_all` has to actually interleave this with `FastSubscriber`
+            // instead of resolving it first just because it was polled first.
+            tokio::task::yield_now().await;
+            self.0.fetch_add(*message, Ordering::SeqCst);
+        }
+    }
+
+    struct FastSubscriber(ReferenceCounted<AtomicU32>);
+    #[async_trait::async_trait]
+    impl Subscriber<u32> for FastSubscriber {
+        async fn update(&self, message: &u32) {
+            self.0.fetch_add(*message, Ordering::SeqCst);
+        }
+    }
+
+    let counter = ReferenceCounted::new(AtomicU32::new(0));
+    let mut publisher = Publisher::<u32>::new();
+    publisher
+        .subscribe(ReferenceCounted::new(SlowSubscriber(counter.clone())))
+        .unwrap();
+    publisher
+        .subscribe(ReferenceCounted::new(FastSubscriber(counter.clone())))
+        .unwrap();
+    publisher.publish_concurrent(5).await;
+    // Both subscribers ran, regardless of which one took longer to finish.
+    assert_eq!(counter.load(Ordering::SeqCst), 10);
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "send", not(feature = "async")))]
+#[test]
+fn test_recv_multiple_waiters() {
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    let mut publisher = Publisher::<i32>::new();
+    let subscription = Arc::new(publisher.subscribe_channel().unwrap());
+
+    let waiter = subscription.clone();
+    let waiter1 = thread::spawn(move || waiter.recv());
+    let waiter = subscription.clone();
+    let waiter2 = thread::spawn(move || waiter.recv());
+
+    // Give both threads a chance to park in `recv` before anything is published.
+    thread::sleep(Duration::from_millis(50));
+    publisher.publish(1);
+    publisher.publish(2);
+
+    let mut received = vec![waiter1.join().unwrap(), waiter2.join().unwrap()];
+    received.sort_unstable();
+    // Both waiters were woken and each got one message; neither waiter was left parked forever.
+    assert_eq!(received, vec![1, 2]);
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "send", feature = "async"))]
+#[tokio::test]
+async fn test_recv_multiple_waiters() {
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    let mut publisher = Publisher::<i32>::new();
+    let subscription = Arc::new(publisher.subscribe_channel().unwrap());
+
+    let waiter = subscription.clone();
+    let waiter1 = thread::spawn(move || waiter.recv());
+    let waiter = subscription.clone();
+    let waiter2 = thread::spawn(move || waiter.recv());
+
+    // Give both threads a chance to park in `recv` before anything is published.
+    thread::sleep(Duration::from_millis(50));
+    publisher.publish(1).await;
+    publisher.publish(2).await;
+
+    let mut received = vec![waiter1.join().unwrap(), waiter2.join().unwrap()];
+    received.sort_unstable();
+    // Both waiters were woken and each got one message; neither waiter was left parked forever.
+    assert_eq!(received, vec![1, 2]);
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_ring_buffer_lagged() {
+    let mut publisher = Publisher::<i32>::new().with_capacity(2);
+    let subscription = publisher.subscribe_channel().unwrap();
+    publisher.publish(1);
+    publisher.publish(2);
+    publisher.publish(3);
+    // The buffer only holds 2 messages, so `1` was dropped to make room for `3`.
+    assert_eq!(subscription.lagged(), 1);
+    assert_eq!(subscription.try_recv(), Some(2));
+    assert_eq!(subscription.try_recv(), Some(3));
+    assert_eq!(subscription.try_recv(), None);
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_ring_buffer_zero_capacity() {
+    // Regression test: a capacity-0 ring buffer used to make `publish` spin forever instead of
+    // simply lagging every message.
+    let mut publisher = Publisher::<i32>::new().with_capacity(0);
+    let subscription = publisher.subscribe_channel().unwrap();
+    publisher.publish(1);
+    publisher.publish(2);
+    assert_eq!(subscription.lagged(), 2);
+    assert_eq!(subscription.try_recv(), None);
+}
+
+#[cfg(test)]
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_ring_buffer_lagged() {
+    let mut publisher = Publisher::<i32>::new().with_capacity(2);
+    let subscription = publisher.subscribe_channel().unwrap();
+    publisher.publish(1).await;
+    publisher.publish(2).await;
+    publisher.publish(3).await;
+    // The buffer only holds 2 messages, so `1` was dropped to make room for `3`.
+    assert_eq!(subscription.lagged(), 1);
+    assert_eq!(subscription.try_recv(), Some(2));
+    assert_eq!(subscription.try_recv(), Some(3));
+    assert_eq!(subscription.try_recv(), None);
+}
+
+#[cfg(test)]
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_ring_buffer_zero_capacity() {
+    // Regression test: a capacity-0 ring buffer used to make `publish` spin forever instead of
+    // simply lagging every message.
+    let mut publisher = Publisher::<i32>::new().with_capacity(0);
+    let subscription = publisher.subscribe_channel().unwrap();
+    publisher.publish(1).await;
+    publisher.publish(2).await;
+    assert_eq!(subscription.lagged(), 2);
+    assert_eq!(subscription.try_recv(), None);
+}
+
+#[cfg(test)]
+#[test]
+fn test_max_subscribers() {
+    let mut publisher = Publisher::<i32>::new().with_max_subscribers(1);
+    let _subscription = publisher.subscribe_channel().unwrap();
+    assert_eq!(
+        publisher.subscribe_channel().err(),
+        Some(PubserveError::MaximumSubscribersReached)
+    );
+}
+
+#[cfg(test)]
+#[cfg(not(feature = "async"))]
+#[test]
+fn test_max_subscribers_counts_across_both_kinds() {
+    struct MySubscriber;
+    impl Subscriber<i32> for MySubscriber {
+        fn update(&self, _message: &i32) {}
+    }
+
+    // A trait-based subscriber alone fills the cap...
+    let mut publisher = Publisher::<i32>::new().with_max_subscribers(1);
+    publisher
+        .subscribe(ReferenceCounted::new(MySubscriber))
+        .unwrap();
+    // ...so both kinds of subscription are rejected, since `subscriber_count` counts trait-based
+    // subscribers and channel-backed subscriptions together.
+    assert_eq!(
+        publisher
+            .subscribe(ReferenceCounted::new(MySubscriber))
+            .err(),
+        Some(PubserveError::MaximumSubscribersReached)
+    );
+    assert_eq!(
+        publisher.subscribe_channel().err(),
+        Some(PubserveError::MaximumSubscribersReached)
+    );
+}
+
+#[cfg(test)]
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_max_subscribers_counts_across_both_kinds() {
+    struct MySubscriber;
+    #[async_trait::async_trait]
+    impl Subscriber<i32> for MySubscriber {
+        async fn update(&self, _message: &i32) {}
+    }
+
+    // A trait-based subscriber alone fills the cap...
+    let mut publisher = Publisher::<i32>::new().with_max_subscribers(1);
+    publisher
+        .subscribe(ReferenceCounted::new(MySubscriber))
+        .unwrap();
+    // ...so both kinds of subscription are rejected, since `subscriber_count` counts trait-based
+    // subscribers and channel-backed subscriptions together.
+    assert_eq!(
+        publisher
+            .subscribe(ReferenceCounted::new(MySubscriber))
+            .err(),
+        Some(PubserveError::MaximumSubscribersReached)
+    );
+    assert_eq!(
+        publisher.subscribe_channel().err(),
+        Some(PubserveError::MaximumSubscribersReached)
+    );
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "sink", feature = "async"))]
+#[tokio::test]
+async fn test_publisher_sink_stream() {
+    use futures::{SinkExt, StreamExt};
+
+    let mut publisher = Publisher::<i32>::new();
+    let mut subscription = publisher.subscribe_channel().unwrap();
+    publisher.send(1).await.unwrap();
+    assert_eq!(subscription.next().await, Some(1));
+    // Dropping the Publisher closes every channel-backed Subscription, ending its Stream.
+    drop(publisher);
+    assert_eq!(subscription.next().await, None);
+}
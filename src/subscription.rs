@@ -0,0 +1,221 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+use std::collections::{HashMap, VecDeque};
+
+#[cfg(feature = "send")]
+use std::sync::Mutex as Lock;
+
+#[cfg(not(feature = "send"))]
+use std::cell::RefCell as Lock;
+
+use crate::ReferenceCounted;
+
+/// Identifies a channel-backed [`Subscription`] within the [`Publisher`] that created it.
+///
+/// [`Publisher`]: crate::Publisher
+pub(crate) type ChannelID = u32;
+
+/// Queue shared between a [`Publisher`] and the [`Subscription`] it was created for.
+///
+/// When `capacity` is set, the queue acts as a ring buffer: once it is full, `publish` never
+/// blocks or waits on a slow subscriber, it instead drops the oldest buffered message to make
+/// room for the new one and bumps `lagged` so the subscriber can tell it missed something.
+pub(crate) struct Inner<T> {
+    queue: VecDeque<T>,
+    capacity: Option<usize>,
+    lagged: u64,
+    /// Threads currently parked in [`Subscription::recv`], if any, so `push` can unpark them
+    /// instead of leaving them to poll in a loop. Multiple `recv` callers can be parked here
+    /// concurrently (a `Subscription` is not single-consumer), so this cannot be a single slot:
+    /// `push` wakes every one of them, and whichever loses the race for the message simply
+    /// re-parks.
+    parked: Vec<std::thread::Thread>,
+    #[cfg(all(feature = "sink", feature = "async"))]
+    closed: bool,
+    #[cfg(all(feature = "sink", feature = "async"))]
+    waker: Option<std::task::Waker>,
+}
+
+impl<T> Inner<T> {
+    fn new(capacity: Option<usize>) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            capacity,
+            lagged: 0,
+            parked: Vec::new(),
+            #[cfg(all(feature = "sink", feature = "async"))]
+            closed: false,
+            #[cfg(all(feature = "sink", feature = "async"))]
+            waker: None,
+        }
+    }
+
+    pub(crate) fn push(&mut self, message: T) {
+        if let Some(capacity) = self.capacity {
+            if capacity == 0 {
+                // A zero-capacity ring buffer holds nothing; every message is immediately lagged
+                // rather than looping forever trying to make room for it.
+                self.lagged += 1;
+                return;
+            }
+            while self.queue.len() >= capacity {
+                self.queue.pop_front();
+                self.lagged += 1;
+            }
+        }
+        self.queue.push_back(message);
+        for thread in self.parked.drain(..) {
+            thread.unpark();
+        }
+        #[cfg(all(feature = "sink", feature = "async"))]
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The registry of channel-backed subscriptions a [`Publisher`] keeps, shared with every
+/// [`Subscription`] it hands out so that dropping one can remove its own entry again.
+pub(crate) type Registry<T> =
+    ReferenceCounted<Lock<HashMap<ChannelID, ReferenceCounted<Lock<Inner<T>>>>>>;
+
+pub(crate) fn new_registry<T>() -> Registry<T> {
+    ReferenceCounted::new(Lock::new(HashMap::new()))
+}
+
+/// The number of channel-backed subscriptions currently registered.
+pub(crate) fn count<T>(registry: &Registry<T>) -> usize {
+    lock(registry).len()
+}
+
+/// Clone `message` into every channel-backed subscription currently registered.
+pub(crate) fn broadcast<T: Clone>(registry: &Registry<T>, message: &T) {
+    for inner in lock(registry).values() {
+        lock(inner).push(message.clone());
+    }
+}
+
+#[cfg(all(feature = "sink", feature = "async"))]
+/// Mark every channel-backed subscription as closed, waking any task polling one so it can drain
+/// its remaining buffered messages and then observe the stream has ended.
+pub(crate) fn close<T>(registry: &Registry<T>) {
+    for inner in lock(registry).values() {
+        let mut inner = lock(inner);
+        inner.closed = true;
+        if let Some(waker) = inner.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(feature = "send")]
+fn lock<T>(lock: &Lock<T>) -> std::sync::MutexGuard<'_, T> {
+    lock.lock().expect("pubserve internal lock poisoned")
+}
+
+#[cfg(not(feature = "send"))]
+fn lock<T>(lock: &Lock<T>) -> std::cell::RefMut<'_, T> {
+    lock.borrow_mut()
+}
+
+/// A pull-based alternative to the push-based [`Subscriber`] trait, returned by
+/// [`Publisher::subscribe_channel`]. A `Subscription` receives a clone of every message the
+/// `Publisher` sends after it was created, without requiring a static type that implements
+/// [`Subscriber`].
+///
+/// Dropping a `Subscription` automatically unsubscribes it from its `Publisher`.
+///
+/// [`Subscriber`]: crate::Subscriber
+/// [`Publisher::subscribe_channel`]: crate::Publisher::subscribe_channel
+pub struct Subscription<T> {
+    pub(crate) id: ChannelID,
+    pub(crate) inner: ReferenceCounted<Lock<Inner<T>>>,
+    pub(crate) registry: Registry<T>,
+}
+
+impl<T> Subscription<T> {
+    pub(crate) fn new(mut id: ChannelID, registry: Registry<T>, capacity: Option<usize>) -> Self {
+        let inner = ReferenceCounted::new(Lock::new(Inner::new(capacity)));
+        {
+            let mut map = lock(&registry);
+            // Cloned publishers share one registry but count ids separately, so skip ids that
+            // are already taken instead of overwriting another subscription's entry.
+            while map.contains_key(&id) {
+                id = id.wrapping_add(1);
+            }
+            map.insert(id, inner.clone());
+        }
+        Self {
+            id,
+            inner,
+            registry,
+        }
+    }
+
+    /// Receive the next message, blocking until one is available.
+    ///
+    /// Parks the calling thread rather than spinning, and is woken as soon as `publish` delivers
+    /// a message. Multiple threads may call `recv` on the same `Subscription` (e.g. shared
+    /// through an `Arc`) concurrently; each message is still only delivered to one of them. Prefer `try_recv` if
+    /// you are polling from an event loop that must not block.
+    pub fn recv(&self) -> T {
+        loop {
+            {
+                let mut inner = lock(&self.inner);
+                if let Some(message) = inner.queue.pop_front() {
+                    return message;
+                }
+                // Register our thread before releasing the lock so that a `push` racing with the
+                // `park` below still unparks us: `push` drains `parked` under the same lock, so
+                // it either runs before we register (we'll see its message ourselves on the next
+                // loop iteration) or after (it unparks us, leaving a permit our `park` call
+                // consumes immediately instead of blocking).
+                inner.parked.push(std::thread::current());
+            }
+            std::thread::park();
+        }
+    }
+
+    /// Receive the next message without blocking, returning `None` if none is queued yet.
+    pub fn try_recv(&self) -> Option<T> {
+        lock(&self.inner).queue.pop_front()
+    }
+
+    /// The number of messages this subscription has missed because its buffer was full when
+    /// they were published. Only ever non-zero for subscriptions created on a [`Publisher`]
+    /// configured with [`Publisher::with_capacity`].
+    ///
+    /// [`Publisher`]: crate::Publisher
+    /// [`Publisher::with_capacity`]: crate::Publisher::with_capacity
+    pub fn lagged(&self) -> u64 {
+        lock(&self.inner).lagged
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        lock(&self.registry).remove(&self.id);
+    }
+}
+
+#[cfg(all(feature = "sink", feature = "async"))]
+impl<T> futures::Stream for Subscription<T> {
+    type Item = T;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<T>> {
+        let mut inner = lock(&self.inner);
+        if let Some(message) = inner.queue.pop_front() {
+            std::task::Poll::Ready(Some(message))
+        } else if inner.closed {
+            std::task::Poll::Ready(None)
+        } else {
+            inner.waker = Some(cx.waker().clone());
+            std::task::Poll::Pending
+        }
+    }
+}
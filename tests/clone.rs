@@ -24,7 +24,7 @@ fn test_clone() {
     let shared = ReferenceCounted::new(MySubscriber {
         vec: RefCell::new(Vec::new()),
     });
-    publisher.subscribe(shared.clone());
+    publisher.subscribe(shared.clone()).unwrap();
     publisher.publish(42);
     assert!(shared.vec.borrow().contains(&42));
     // Test, if cloning the publisher, then dropping the original publisher, still allows the